@@ -0,0 +1,275 @@
+//! Support for the AWS CLI `~/.aws/config` file, which stores per-profile
+//! settings (region, output format, role ARNs, ...) alongside the bare
+//! credentials kept in `~/.aws/credentials`.
+//!
+//! Sections in this file are written as `[profile name]` for every profile
+//! except `default`, which is written bare as `[default]`. This mirrors the
+//! format produced and consumed by the official AWS CLI.
+
+use regex::Regex;
+use std::{
+    collections::HashMap,
+    fs::OpenOptions,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use crate::{AWSCredentials, Credentials, Error};
+
+/// Arbitrary key/value settings for a single profile, e.g. `region`,
+/// `output`, `role_arn`, `source_profile`.
+pub type ProfileSettings = HashMap<String, String>;
+
+/// A profile's credentials merged with its config-file settings, the same
+/// way the AWS CLI resolves a profile across both files.
+#[derive(Clone, Debug, Default)]
+pub struct Profile {
+    pub credentials: Option<Credentials>,
+    pub settings: ProfileSettings,
+}
+
+/// Contains a mapping of profiles to their `~/.aws/config` settings.
+/// Provides methods to load from, and save to, the default AWS config file.
+#[derive(Debug)]
+pub struct AWSConfig {
+    file_path: String,
+    profiles: HashMap<String, ProfileSettings>,
+}
+
+impl AWSConfig {
+    /// Creates a new, empty AWSConfig instance.
+    pub fn new<P: AsRef<Path>>(path: P) -> AWSConfig {
+        AWSConfig {
+            file_path: path.as_ref().to_str().unwrap().to_string(),
+            profiles: HashMap::new(),
+        }
+    }
+
+    /// Gets the settings for the specified profile.
+    pub fn get_profile(&self, profile: &str) -> Option<ProfileSettings> {
+        self.profiles.get(profile).cloned()
+    }
+
+    /// Gets a mutable reference to the settings for the specified profile.
+    pub fn get_profile_mut(&mut self, profile: &str) -> Option<&mut ProfileSettings> {
+        self.profiles.get_mut(profile)
+    }
+
+    /// Sets the settings for the specified profile.
+    pub fn set_profile(&mut self, profile: &str, settings: &ProfileSettings) {
+        self.profiles.insert(profile.to_string(), settings.clone());
+    }
+
+    /// Returns a profile's settings, creating an empty entry if it does not exist.
+    pub fn with_profile(&mut self, profile: &str) -> &mut ProfileSettings {
+        self.profiles.entry(profile.to_string()).or_default()
+    }
+
+    /// Checks if the specified profile exists.
+    pub fn exists(&self, profile: &str) -> bool {
+        self.profiles.contains_key(profile)
+    }
+
+    /// Removes the specified profile.
+    pub fn remove_profile(&mut self, profile: &str) -> Option<ProfileSettings> {
+        self.profiles.remove(profile)
+    }
+
+    /// Merges this profile's settings with the secret keys held in `credentials`,
+    /// the same way the AWS CLI resolves a profile across both files.
+    pub fn get_merged_profile(
+        &self,
+        credentials: &AWSCredentials,
+        profile: &str,
+    ) -> Option<Profile> {
+        let settings = self.profiles.get(profile).cloned();
+        let creds = credentials.get_profile(profile);
+
+        if settings.is_none() && creds.is_none() {
+            return None;
+        }
+
+        Some(Profile {
+            credentials: creds,
+            settings: settings.unwrap_or_default(),
+        })
+    }
+
+    /// Load config from the default AWS config file location (`~/.aws/config`).
+    pub fn load() -> Result<AWSConfig, Error> {
+        Self::load_from(&format!(
+            "{}/.aws/config",
+            dirs::home_dir().unwrap().to_str().unwrap()
+        ))
+    }
+
+    /// Load config from the specified file path.
+    pub fn load_from(file_path: &str) -> Result<AWSConfig, Error> {
+        let file = std::fs::read_to_string(file_path)
+            .map_err(|_| Error::FileNotFound(file_path.to_string()))?;
+
+        let profiles = Self::parse(file)?;
+
+        Ok(AWSConfig {
+            file_path: file_path.to_string(),
+            profiles,
+        })
+    }
+
+    fn parse(data: String) -> Result<HashMap<String, ProfileSettings>, Error> {
+        let section_re = Regex::new(r"^\[(profile )?([^\]]+)\]$").map_err(|_| Error::FailedToParse)?;
+
+        let mut profiles = HashMap::new();
+        let mut current_section: Option<String> = None;
+        let mut current_settings = ProfileSettings::new();
+
+        for line in data.lines() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(captures) = section_re.captures(line) {
+                if let Some(section) = current_section.take() {
+                    profiles.insert(section, std::mem::take(&mut current_settings));
+                }
+                current_section = Some(captures[2].to_string());
+                continue;
+            }
+
+            let parts: Vec<&str> = line.splitn(2, '=').collect();
+            if parts.len() == 2 && current_section.is_some() {
+                let key = parts[0].trim().to_string();
+                let value = parts[1].trim().to_string();
+                current_settings.insert(key, value);
+            }
+        }
+
+        if let Some(section) = current_section.take() {
+            profiles.insert(section, current_settings);
+        }
+
+        Ok(profiles)
+    }
+
+    /// Write config back to the file path this instance was created with or loaded from.
+    pub fn write(&self) -> Result<(), Error> {
+        self.write_to(Path::new(&self.file_path))
+    }
+
+    /// Write config to the specified file path.
+    pub fn write_to<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)
+            .map_err(|_| Error::FileNotFound(self.file_path.to_string()))?;
+
+        let mut writer = BufWriter::new(file);
+
+        for (section, settings) in &self.profiles {
+            if section == "default" {
+                writeln!(writer, "[default]").unwrap();
+            } else {
+                writeln!(writer, "[profile {}]", section).unwrap();
+            }
+
+            for (key, value) in settings {
+                writeln!(writer, "{} = {}", key, value).unwrap();
+            }
+
+            writeln!(writer).unwrap();
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::AWSConfig;
+
+    #[test]
+    fn can_load_config() {
+        let temp_aws_config = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(
+            temp_aws_config.path(),
+            r#"
+[default]
+region = us-east-1
+output = json
+
+[profile dev]
+region = eu-west-1
+role_arn = arn:aws:iam::123456789012:role/dev
+source_profile = default
+"#,
+        )
+        .unwrap();
+
+        let temp_aws_config_path = temp_aws_config.path().to_str().unwrap();
+        let config = AWSConfig::load_from(temp_aws_config_path).unwrap();
+
+        let default_profile = config.get_profile("default").unwrap();
+        assert_eq!(default_profile.get("region").unwrap(), "us-east-1");
+        assert_eq!(default_profile.get("output").unwrap(), "json");
+
+        let dev_profile = config.get_profile("dev").unwrap();
+        assert_eq!(dev_profile.get("region").unwrap(), "eu-west-1");
+        assert_eq!(
+            dev_profile.get("role_arn").unwrap(),
+            "arn:aws:iam::123456789012:role/dev"
+        );
+        assert_eq!(dev_profile.get("source_profile").unwrap(), "default");
+    }
+
+    #[test]
+    fn can_write_config() {
+        let temp_aws_config = tempfile::NamedTempFile::new().unwrap();
+        let temp_aws_config_path = temp_aws_config.path().to_str().unwrap();
+
+        let mut config = AWSConfig::new(temp_aws_config_path);
+        config
+            .with_profile("dev")
+            .insert("region".to_string(), "eu-west-1".to_string());
+        config.write().unwrap();
+
+        let config = AWSConfig::load_from(temp_aws_config_path).unwrap();
+        let dev_profile = config.get_profile("dev").unwrap();
+        assert_eq!(dev_profile.get("region").unwrap(), "eu-west-1");
+    }
+
+    #[test]
+    fn get_merged_profile_combines_both_files() {
+        use crate::AWSCredentials;
+
+        let temp_creds = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(
+            temp_creds.path(),
+            r#"
+[dev]
+aws_access_key_id = ACCESS_KEY
+aws_secret_access_key = SECRET_KEY
+"#,
+        )
+        .unwrap();
+        let credentials = AWSCredentials::load_from(temp_creds.path().to_str().unwrap()).unwrap();
+
+        let temp_config = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(
+            temp_config.path(),
+            r#"
+[profile dev]
+region = eu-west-1
+"#,
+        )
+        .unwrap();
+        let config = AWSConfig::load_from(temp_config.path().to_str().unwrap()).unwrap();
+
+        let merged = config.get_merged_profile(&credentials, "dev").unwrap();
+        assert_eq!(merged.credentials.unwrap().access_key_id, "ACCESS_KEY");
+        assert_eq!(merged.settings.get("region").unwrap(), "eu-west-1");
+    }
+}
@@ -17,6 +17,7 @@
 //! }
 //! ```
 
+use chrono::{DateTime, Utc};
 use derive_builder::Builder;
 use dirs::home_dir;
 use std::{
@@ -30,17 +31,67 @@ use std::{
 #[cfg(feature = "async_std")]
 use async_std::io::WriteExt;
 
+mod config;
+pub use config::{AWSConfig, Profile, ProfileSettings};
+
+mod provider;
+pub use provider::{
+    AnonymousProvider, ChainProvider, CredentialProvider, EnvironmentProvider, ProfileProvider,
+    StaticProvider,
+};
+
+mod secret;
+pub use secret::Secret;
+
+#[cfg(any(feature = "rusoto", feature = "aws_sdk"))]
+mod sts;
+#[cfg(any(feature = "rusoto", feature = "aws_sdk"))]
+pub use sts::AssumeRoleParams;
+
+mod resolve;
+
+#[cfg(feature = "encrypted")]
+mod vault;
+
 /// Represents AWS credentials with fields for access and secret keys.
+///
+/// `secret_access_key` and `session_token` are wrapped in [`Secret`], so
+/// `{:?}`-formatting a `Credentials` value never prints the real secret.
 #[derive(Clone, Builder, Debug, Default)]
 pub struct Credentials {
-    pub secret_access_key: String,
+    #[builder(setter(into))]
+    pub secret_access_key: Secret,
     pub access_key_id: String,
     #[builder(setter(into, strip_option), default)]
-    pub session_token: Option<String>,
+    pub session_token: Option<Secret>,
+    /// When these credentials expire, for temporary STS session credentials.
+    /// Serialized as the `aws_expiration` key.
+    #[builder(setter(into, strip_option), default)]
+    pub expiration: Option<DateTime<Utc>>,
 }
 
 impl Credentials {
-    pub(crate) fn set_secret_access_key(&mut self, value: String) {
+    /// Returns a set of anonymous credentials: an empty access key and secret,
+    /// matching the "anonymous credentials for public objects" pattern used
+    /// to address public buckets without signing requests.
+    pub fn anonymous() -> Credentials {
+        Credentials {
+            access_key_id: String::new(),
+            secret_access_key: Secret::default(),
+            session_token: None,
+            expiration: None,
+        }
+    }
+
+    /// Returns `true` if these credentials carry an expiration and it has passed.
+    pub fn is_expired(&self) -> bool {
+        match self.expiration {
+            Some(expiration) => expiration <= Utc::now(),
+            None => false,
+        }
+    }
+
+    pub(crate) fn set_secret_access_key(&mut self, value: Secret) {
         self.secret_access_key = value;
     }
 
@@ -48,18 +99,41 @@ impl Credentials {
         self.access_key_id = value;
     }
 
-    pub(crate) fn set_session_token(&mut self, value: Option<String>) {
+    pub(crate) fn set_session_token(&mut self, value: Option<Secret>) {
         self.session_token = value;
     }
+
+    pub(crate) fn set_anonymous(&mut self) {
+        self.access_key_id = String::new();
+        self.secret_access_key = Secret::default();
+        self.session_token = None;
+    }
+}
+
+/// Identifies credentials that represent the absence of a secret, i.e. the
+/// anonymous credentials used for accessing public resources.
+pub trait Anonymous {
+    fn is_anonymous(&self) -> bool;
+}
+
+impl Anonymous for Credentials {
+    fn is_anonymous(&self) -> bool {
+        self.access_key_id.is_empty() && self.secret_access_key.is_empty()
+    }
 }
 
 #[cfg(feature = "rusoto")]
 impl From<rusoto_sts::Credentials> for Credentials {
     fn from(credentials: rusoto_sts::Credentials) -> Self {
+        let expiration = DateTime::parse_from_rfc3339(&credentials.expiration)
+            .map(|dt| dt.with_timezone(&Utc))
+            .ok();
+
         Credentials {
-            secret_access_key: credentials.secret_access_key,
+            secret_access_key: credentials.secret_access_key.into(),
             access_key_id: credentials.access_key_id,
-            session_token: Some(credentials.session_token),
+            session_token: Some(credentials.session_token.into()),
+            expiration,
         }
     }
 }
@@ -69,12 +143,16 @@ impl TryFrom<aws_sdk_sts::types::Credentials> for Credentials {
     type Error = &'static str;
 
     fn try_from(credentials: aws_sdk_sts::types::Credentials) -> Result<Self, Self::Error> {
+        let expiration = DateTime::from_timestamp(credentials.expiration.secs(), 0);
+
         Ok(Credentials {
             secret_access_key: credentials
                 .secret_access_key
-                .ok_or("Missing secret access key")?,
+                .ok_or("Missing secret access key")?
+                .into(),
             access_key_id: credentials.access_key_id.ok_or("Missing access key id")?,
-            session_token: credentials.session_token,
+            session_token: credentials.session_token.map(Into::into),
+            expiration,
         })
     }
 }
@@ -83,6 +161,13 @@ impl TryFrom<aws_sdk_sts::types::Credentials> for Credentials {
 pub enum Error {
     FileNotFound(String),
     FailedToParse,
+    /// Returned by [`AWSCredentials::load_encrypted`](crate::AWSCredentials::load_encrypted)
+    /// when the given passphrase does not match the vault's `verify_blob`.
+    InvalidPassphrase,
+    /// Returned by [`AWSConfig::resolve_profile`](crate::AWSConfig::resolve_profile)
+    /// when a profile's `source_profile` chain revisits a profile it already
+    /// passed through, instead of recursing forever.
+    SourceProfileCycle(String),
 }
 
 impl fmt::Display for Error {
@@ -90,6 +175,10 @@ impl fmt::Display for Error {
         match self {
             Error::FileNotFound(path) => write!(f, "File not found: {}", path),
             Error::FailedToParse => write!(f, "Failed to parse"),
+            Error::InvalidPassphrase => write!(f, "Invalid passphrase"),
+            Error::SourceProfileCycle(profile) => {
+                write!(f, "source_profile cycle detected at profile: {}", profile)
+            }
         }
     }
 }
@@ -233,6 +322,11 @@ impl AWSCredentials {
                             "aws_session_token" => {
                                 builder.session_token(value.to_string());
                             }
+                            "aws_expiration" => {
+                                if let Ok(expiration) = DateTime::parse_from_rfc3339(value) {
+                                    builder.expiration(expiration.with_timezone(&Utc));
+                                }
+                            }
                             _ => (),
                         }
                     }
@@ -269,12 +363,16 @@ impl AWSCredentials {
             writeln!(
                 writer,
                 "aws_secret_access_key = {}",
-                creds.secret_access_key
+                creds.secret_access_key.reveal()
             )
             .unwrap();
 
             if let Some(session_token) = &creds.session_token {
-                writeln!(writer, "aws_session_token = {}", session_token).unwrap();
+                writeln!(writer, "aws_session_token = {}", session_token.reveal()).unwrap();
+            }
+
+            if let Some(expiration) = &creds.expiration {
+                writeln!(writer, "aws_expiration = {}", expiration.to_rfc3339()).unwrap();
             }
 
             writeln!(writer).unwrap();
@@ -316,14 +414,29 @@ impl AWSCredentials {
                 .unwrap();
             writer
                 .write(
-                    &format!("aws_secret_access_key = {}\n", creds.secret_access_key).into_bytes(),
+                    &format!(
+                        "aws_secret_access_key = {}\n",
+                        creds.secret_access_key.reveal()
+                    )
+                    .into_bytes(),
                 )
                 .await
                 .unwrap();
 
             if let Some(session_token) = &creds.session_token {
                 writer
-                    .write(&format!("aws_session_token = {}\n", session_token).into_bytes())
+                    .write(
+                        &format!("aws_session_token = {}\n", session_token.reveal()).into_bytes(),
+                    )
+                    .await
+                    .unwrap();
+            }
+
+            if let Some(expiration) = &creds.expiration {
+                writer
+                    .write(
+                        &format!("aws_expiration = {}\n", expiration.to_rfc3339()).into_bytes(),
+                    )
                     .await
                     .unwrap();
             }
@@ -355,7 +468,7 @@ impl<'a> CredentialsSetter<'a> {
 
     pub fn set_secret_access_key<V>(&'a mut self, value: V) -> &'a mut CredentialsSetter<'a>
     where
-        V: Into<String>,
+        V: Into<Secret>,
     {
         if let Some(credentials) = self.aws_credentials.get_profile_mut(&self.profile_name) {
             credentials.set_secret_access_key(value.into());
@@ -375,7 +488,7 @@ impl<'a> CredentialsSetter<'a> {
 
     pub fn set_session_token<V>(&'a mut self, value: Option<V>) -> &'a mut CredentialsSetter<'a>
     where
-        V: Into<String>,
+        V: Into<Secret>,
     {
         if let Some(credentials) = self.aws_credentials.get_profile_mut(&self.profile_name) {
             credentials.set_session_token(value.map(Into::into));
@@ -385,20 +498,55 @@ impl<'a> CredentialsSetter<'a> {
 
     pub fn clear_session_token<V>(&'a mut self) -> &'a mut CredentialsSetter<'a>
     where
-        V: Into<String>,
+        V: Into<Secret>,
     {
         if let Some(credentials) = self.aws_credentials.get_profile_mut(&self.profile_name) {
             credentials.set_session_token(None);
         };
         self
     }
+
+    /// Clears the access key, secret key, and session token, marking the
+    /// profile as anonymous.
+    pub fn set_anonymous(&'a mut self) -> &'a mut CredentialsSetter<'a> {
+        if let Some(credentials) = self.aws_credentials.get_profile_mut(&self.profile_name) {
+            credentials.set_anonymous();
+        };
+        self
+    }
 }
 
 #[cfg(test)]
 mod test {
-    use super::AWSCredentials;
+    use super::{Anonymous, AWSCredentials, Credentials};
+    use chrono::{Duration, Utc};
     use tempfile;
 
+    #[test]
+    fn is_expired_is_false_without_an_expiration() {
+        let credentials = Credentials {
+            access_key_id: "ACCESS_KEY".to_string(),
+            secret_access_key: "SECRET_KEY".into(),
+            session_token: None,
+            expiration: None,
+        };
+        assert!(!credentials.is_expired());
+    }
+
+    #[test]
+    fn is_expired_reflects_whether_expiration_has_passed() {
+        let mut credentials = Credentials {
+            access_key_id: "ACCESS_KEY".to_string(),
+            secret_access_key: "SECRET_KEY".into(),
+            session_token: None,
+            expiration: Some(Utc::now() + Duration::seconds(60)),
+        };
+        assert!(!credentials.is_expired());
+
+        credentials.expiration = Some(Utc::now() - Duration::seconds(60));
+        assert!(credentials.is_expired());
+    }
+
     #[test]
     fn can_load_credentials() {
         let temp_aws_credentials = tempfile::NamedTempFile::new().unwrap();
@@ -417,7 +565,7 @@ aws_secret_access_key = SECRET_KEY
         let credentials = AWSCredentials::load_from(temp_aws_credentials_path).unwrap();
         let default_profile = credentials.get_profile("default").unwrap();
         assert_eq!(default_profile.access_key_id, "ACCESS_KEY");
-        assert_eq!(default_profile.secret_access_key, "SECRET_KEY");
+        assert_eq!(default_profile.secret_access_key.reveal(), "SECRET_KEY");
     }
 
     #[test]
@@ -435,13 +583,30 @@ aws_secret_access_key = SECRET_KEY
         let credentials = AWSCredentials::load_from(temp_aws_credentials_path).unwrap();
         let default_profile = credentials.get_profile("default").unwrap();
         assert_eq!(default_profile.access_key_id, "ACCESS_KEY");
-        assert_eq!(default_profile.secret_access_key, "SECRET_KEY");
+        assert_eq!(default_profile.secret_access_key.reveal(), "SECRET_KEY");
         assert_eq!(
-            default_profile.session_token,
-            Some("SESSION_TOKEN".to_string())
+            default_profile.session_token.unwrap().reveal(),
+            "SESSION_TOKEN"
         );
     }
 
+    #[test]
+    fn set_anonymous_clears_keys_and_token() {
+        let temp_aws_credentials = tempfile::NamedTempFile::new().unwrap();
+        let temp_aws_credentials_path = temp_aws_credentials.path().to_str().unwrap();
+        let mut credentials = AWSCredentials::new(temp_aws_credentials_path);
+        credentials
+            .with_profile("default")
+            .set_access_key_id("ACCESS_KEY")
+            .set_secret_access_key("SECRET_KEY")
+            .set_session_token(Some("SESSION_TOKEN".to_string()))
+            .set_anonymous();
+
+        let default_profile = credentials.get_profile("default").unwrap();
+        assert!(default_profile.is_anonymous());
+        assert_eq!(default_profile.session_token, None);
+    }
+
     #[cfg(feature = "async_std")]
     #[tokio::test]
     async fn can_load_credentials_async() {
@@ -463,7 +628,7 @@ aws_secret_access_key = SECRET_KEY
             .unwrap();
         let default_profile = credentials.get_profile("default").unwrap();
         assert_eq!(default_profile.access_key_id, "ACCESS_KEY");
-        assert_eq!(default_profile.secret_access_key, "SECRET_KEY");
+        assert_eq!(default_profile.secret_access_key.reveal(), "SECRET_KEY");
     }
 
     #[cfg(feature = "async_std")]
@@ -484,10 +649,10 @@ aws_secret_access_key = SECRET_KEY
             .unwrap();
         let default_profile = credentials.get_profile("default").unwrap();
         assert_eq!(default_profile.access_key_id, "ACCESS_KEY");
-        assert_eq!(default_profile.secret_access_key, "SECRET_KEY");
+        assert_eq!(default_profile.secret_access_key.reveal(), "SECRET_KEY");
         assert_eq!(
-            default_profile.session_token,
-            Some("SESSION_TOKEN".to_string())
+            default_profile.session_token.unwrap().reveal(),
+            "SESSION_TOKEN"
         );
     }
 }
@@ -0,0 +1,187 @@
+//! A chain of credential providers, modeled on the provider chains used by
+//! the rusoto/AWS SDK credential ecosystems: each provider knows how to
+//! resolve [`Credentials`] from one source (environment variables, a named
+//! profile, a static value, ...), and a [`ChainProvider`] tries each in turn
+//! until one succeeds.
+
+use std::env;
+
+use crate::{AWSCredentials, Credentials, Error};
+
+/// Resolves [`Credentials`] from a single source.
+pub trait CredentialProvider {
+    fn resolve(&self) -> Result<Credentials, Error>;
+}
+
+/// Reads credentials from `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY`, and
+/// the optional `AWS_SESSION_TOKEN` environment variables.
+#[derive(Debug, Default)]
+pub struct EnvironmentProvider;
+
+impl CredentialProvider for EnvironmentProvider {
+    fn resolve(&self) -> Result<Credentials, Error> {
+        let access_key_id = env::var("AWS_ACCESS_KEY_ID").map_err(|_| Error::FailedToParse)?;
+        let secret_access_key =
+            env::var("AWS_SECRET_ACCESS_KEY").map_err(|_| Error::FailedToParse)?;
+        let session_token = env::var("AWS_SESSION_TOKEN").ok();
+
+        Ok(Credentials {
+            access_key_id,
+            secret_access_key: secret_access_key.into(),
+            session_token: session_token.map(Into::into),
+            expiration: None,
+        })
+    }
+}
+
+/// Reads credentials for a named profile from the shared credentials file,
+/// honoring the `AWS_PROFILE` and `AWS_SHARED_CREDENTIALS_FILE` environment
+/// overrides the same way the AWS CLI does.
+#[derive(Debug)]
+pub struct ProfileProvider {
+    profile: Option<String>,
+}
+
+impl ProfileProvider {
+    /// Uses the given profile, ignoring `AWS_PROFILE`.
+    pub fn with_profile<V: Into<String>>(profile: V) -> ProfileProvider {
+        ProfileProvider {
+            profile: Some(profile.into()),
+        }
+    }
+
+    /// Uses `AWS_PROFILE`, falling back to `default`.
+    pub fn new() -> ProfileProvider {
+        ProfileProvider { profile: None }
+    }
+
+    fn profile_name(&self) -> String {
+        self.profile
+            .clone()
+            .or_else(|| env::var("AWS_PROFILE").ok())
+            .unwrap_or_else(|| "default".to_string())
+    }
+
+    fn credentials_file(&self) -> Result<AWSCredentials, Error> {
+        match env::var("AWS_SHARED_CREDENTIALS_FILE") {
+            Ok(path) => AWSCredentials::load_from(&path),
+            Err(_) => AWSCredentials::load(),
+        }
+    }
+}
+
+impl Default for ProfileProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CredentialProvider for ProfileProvider {
+    fn resolve(&self) -> Result<Credentials, Error> {
+        let credentials = self.credentials_file()?;
+        credentials
+            .get_profile(&self.profile_name())
+            .ok_or(Error::FailedToParse)
+    }
+}
+
+/// Wraps a fixed, in-memory set of credentials.
+#[derive(Debug, Clone)]
+pub struct StaticProvider {
+    credentials: Credentials,
+}
+
+impl StaticProvider {
+    pub fn new(credentials: Credentials) -> StaticProvider {
+        StaticProvider { credentials }
+    }
+}
+
+impl CredentialProvider for StaticProvider {
+    fn resolve(&self) -> Result<Credentials, Error> {
+        Ok(self.credentials.clone())
+    }
+}
+
+/// Always resolves to [`Credentials::anonymous`], for public-resource
+/// workflows that require no authentication.
+#[derive(Debug, Default)]
+pub struct AnonymousProvider;
+
+impl CredentialProvider for AnonymousProvider {
+    fn resolve(&self) -> Result<Credentials, Error> {
+        Ok(Credentials::anonymous())
+    }
+}
+
+/// Tries each provider in order and returns the first successful resolution.
+pub struct ChainProvider {
+    providers: Vec<Box<dyn CredentialProvider>>,
+}
+
+impl ChainProvider {
+    pub fn new(providers: Vec<Box<dyn CredentialProvider>>) -> ChainProvider {
+        ChainProvider { providers }
+    }
+}
+
+impl CredentialProvider for ChainProvider {
+    fn resolve(&self) -> Result<Credentials, Error> {
+        for provider in &self.providers {
+            if let Ok(credentials) = provider.resolve() {
+                return Ok(credentials);
+            }
+        }
+
+        Err(Error::FailedToParse)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn static_provider_resolves_the_given_credentials() {
+        let credentials = Credentials {
+            access_key_id: "ACCESS_KEY".to_string(),
+            secret_access_key: "SECRET_KEY".into(),
+            session_token: None,
+            expiration: None,
+        };
+        let provider = StaticProvider::new(credentials.clone());
+        assert_eq!(provider.resolve().unwrap().access_key_id, "ACCESS_KEY");
+    }
+
+    #[test]
+    fn anonymous_provider_resolves_anonymous_credentials() {
+        use crate::Anonymous;
+
+        let provider = AnonymousProvider;
+        assert!(provider.resolve().unwrap().is_anonymous());
+    }
+
+    struct FailingProvider;
+
+    impl CredentialProvider for FailingProvider {
+        fn resolve(&self) -> Result<Credentials, Error> {
+            Err(Error::FailedToParse)
+        }
+    }
+
+    #[test]
+    fn chain_provider_falls_back_to_the_next_provider() {
+        let credentials = Credentials {
+            access_key_id: "ACCESS_KEY".to_string(),
+            secret_access_key: "SECRET_KEY".into(),
+            session_token: None,
+            expiration: None,
+        };
+        let chain = ChainProvider::new(vec![
+            Box::new(FailingProvider),
+            Box::new(StaticProvider::new(credentials)),
+            Box::new(AnonymousProvider),
+        ]);
+        assert_eq!(chain.resolve().unwrap().access_key_id, "ACCESS_KEY");
+    }
+}
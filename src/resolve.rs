@@ -0,0 +1,202 @@
+//! Follows the AWS CLI's profile resolution rules: a profile may be static
+//! (plain secret keys in the credentials file), backed by a
+//! `credential_process` command, or chained through a `source_profile` +
+//! `role_arn` pair. [`AWSConfig::resolve_profile`] transparently follows
+//! whichever indirection applies and returns usable [`Credentials`].
+
+use std::{collections::HashSet, process::Command};
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use crate::{AWSConfig, AWSCredentials, Credentials, Error};
+
+#[cfg(any(feature = "rusoto", feature = "aws_sdk"))]
+use crate::sts::AssumeRoleParams;
+
+/// The JSON shape a `credential_process` command must print to stdout.
+#[derive(Deserialize)]
+struct CredentialProcessOutput {
+    #[serde(rename = "AccessKeyId")]
+    access_key_id: String,
+    #[serde(rename = "SecretAccessKey")]
+    secret_access_key: String,
+    #[serde(rename = "SessionToken")]
+    session_token: Option<String>,
+    #[serde(rename = "Expiration")]
+    expiration: Option<String>,
+}
+
+impl From<CredentialProcessOutput> for Credentials {
+    fn from(output: CredentialProcessOutput) -> Self {
+        let expiration = output
+            .expiration
+            .and_then(|value| DateTime::parse_from_rfc3339(&value).ok())
+            .map(|dt| dt.with_timezone(&Utc));
+
+        Credentials {
+            access_key_id: output.access_key_id,
+            secret_access_key: output.secret_access_key.into(),
+            session_token: output.session_token.map(Into::into),
+            expiration,
+        }
+    }
+}
+
+fn run_credential_process(command: &str) -> Result<Credentials, Error> {
+    let output = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .output()
+        .map_err(|_| Error::FailedToParse)?;
+
+    if !output.status.success() {
+        return Err(Error::FailedToParse);
+    }
+
+    let parsed: CredentialProcessOutput =
+        serde_json::from_slice(&output.stdout).map_err(|_| Error::FailedToParse)?;
+
+    Ok(parsed.into())
+}
+
+impl AWSConfig {
+    /// Resolves a profile's usable [`Credentials`], transparently following
+    /// `credential_process` and `source_profile` + `role_arn` indirections
+    /// the same way the AWS CLI does. Falls back to the profile's static
+    /// secret keys in `credentials` when neither applies.
+    pub async fn resolve_profile(
+        &self,
+        credentials: &AWSCredentials,
+        profile: &str,
+    ) -> Result<Credentials, Error> {
+        let mut visited = HashSet::new();
+        self.resolve_profile_visited(credentials, profile, &mut visited)
+            .await
+    }
+
+    /// Inner implementation of [`Self::resolve_profile`] that tracks which
+    /// profiles the current `source_profile` chain has already passed
+    /// through, so a cycle (including a profile that names itself) fails
+    /// cleanly instead of recursing until the stack overflows.
+    async fn resolve_profile_visited(
+        &self,
+        credentials: &AWSCredentials,
+        profile: &str,
+        visited: &mut HashSet<String>,
+    ) -> Result<Credentials, Error> {
+        if !visited.insert(profile.to_string()) {
+            return Err(Error::SourceProfileCycle(profile.to_string()));
+        }
+
+        let settings = self.get_profile(profile).unwrap_or_default();
+
+        if let Some(command) = settings.get("credential_process") {
+            return run_credential_process(command);
+        }
+
+        if let (Some(source_profile), Some(role_arn)) =
+            (settings.get("source_profile"), settings.get("role_arn"))
+        {
+            return self
+                .assume_role_chain(credentials, source_profile, role_arn, profile, visited)
+                .await;
+        }
+
+        credentials.get_profile(profile).ok_or(Error::FailedToParse)
+    }
+
+    #[cfg(any(feature = "rusoto", feature = "aws_sdk"))]
+    async fn assume_role_chain(
+        &self,
+        credentials: &AWSCredentials,
+        source_profile: &str,
+        role_arn: &str,
+        target_profile: &str,
+        visited: &mut HashSet<String>,
+    ) -> Result<Credentials, Error> {
+        let base_credentials =
+            Box::pin(self.resolve_profile_visited(credentials, source_profile, visited)).await?;
+
+        // `assume_role` signs with `params.source_profile`'s credentials, so
+        // stage the resolved base credentials under that name on a scratch
+        // store and pass the name through explicitly, rather than mutating
+        // process-wide environment variables.
+        let mut scratch = AWSCredentials::new("");
+        scratch.set_profile(source_profile, &base_credentials);
+
+        let mut params = AssumeRoleParams::new(role_arn, format!("{}-session", target_profile));
+        params.source_profile = Some(source_profile.to_string());
+        scratch.assume_role(target_profile, &params).await?;
+
+        scratch
+            .get_profile(target_profile)
+            .ok_or(Error::FailedToParse)
+    }
+
+    #[cfg(not(any(feature = "rusoto", feature = "aws_sdk")))]
+    async fn assume_role_chain(
+        &self,
+        _credentials: &AWSCredentials,
+        _source_profile: &str,
+        _role_arn: &str,
+        _target_profile: &str,
+        _visited: &mut HashSet<String>,
+    ) -> Result<Credentials, Error> {
+        Err(Error::FailedToParse)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn run_credential_process_parses_valid_output() {
+        let credentials = run_credential_process(
+            r#"echo '{"AccessKeyId":"ACCESS_KEY","SecretAccessKey":"SECRET_KEY","SessionToken":"SESSION_TOKEN","Expiration":"2030-01-01T00:00:00Z"}'"#,
+        )
+        .unwrap();
+
+        assert_eq!(credentials.access_key_id, "ACCESS_KEY");
+        assert_eq!(credentials.secret_access_key.reveal(), "SECRET_KEY");
+        assert_eq!(
+            credentials.session_token.unwrap().reveal(),
+            "SESSION_TOKEN"
+        );
+        assert!(credentials.expiration.is_some());
+    }
+
+    #[test]
+    fn run_credential_process_fails_on_malformed_output() {
+        let result = run_credential_process("echo 'not json'");
+        assert!(matches!(result, Err(Error::FailedToParse)));
+    }
+
+    #[test]
+    fn run_credential_process_fails_on_nonzero_exit() {
+        let result = run_credential_process("exit 1");
+        assert!(matches!(result, Err(Error::FailedToParse)));
+    }
+
+    // The stub `assume_role_chain` built with neither `rusoto` nor `aws_sdk`
+    // fails immediately without re-entering `resolve_profile_visited`, so it
+    // never reaches the cycle check this test exercises.
+    #[cfg(any(feature = "rusoto", feature = "aws_sdk"))]
+    #[tokio::test]
+    async fn resolve_profile_detects_a_self_referencing_cycle() {
+        let mut config = AWSConfig::new("");
+        let mut settings = crate::ProfileSettings::new();
+        settings.insert("source_profile".to_string(), "looped".to_string());
+        settings.insert(
+            "role_arn".to_string(),
+            "arn:aws:iam::123456789012:role/test".to_string(),
+        );
+        config.set_profile("looped", &settings);
+
+        let credentials = AWSCredentials::new("");
+        let result = config.resolve_profile(&credentials, "looped").await;
+
+        assert!(matches!(result, Err(Error::SourceProfileCycle(profile)) if profile == "looped"));
+    }
+}
@@ -0,0 +1,72 @@
+//! A small wrapper that keeps secret material out of `Debug`/`Display` output,
+//! so logging or panicking on a [`Credentials`](crate::Credentials) value
+//! can't leak the underlying secret access key or session token.
+
+use std::fmt;
+
+/// A string that redacts itself in `Debug`/`Display` output. Use
+/// [`Secret::reveal`] to access the real value, e.g. when writing it to disk.
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct Secret(String);
+
+impl Secret {
+    pub fn new<V: Into<String>>(value: V) -> Secret {
+        Secret(value.into())
+    }
+
+    /// Returns the real, unredacted value.
+    pub fn reveal(&self) -> &str {
+        &self.0
+    }
+
+    /// Alias for [`Secret::reveal`].
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "****")
+    }
+}
+
+impl fmt::Display for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "****")
+    }
+}
+
+impl From<String> for Secret {
+    fn from(value: String) -> Self {
+        Secret(value)
+    }
+}
+
+impl From<&str> for Secret {
+    fn from(value: &str) -> Self {
+        Secret(value.to_string())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Secret;
+
+    #[test]
+    fn debug_and_display_redact_the_value() {
+        let secret = Secret::new("SECRET_KEY");
+        assert_eq!(format!("{:?}", secret), "****");
+        assert_eq!(format!("{}", secret), "****");
+    }
+
+    #[test]
+    fn reveal_returns_the_real_value() {
+        let secret = Secret::new("SECRET_KEY");
+        assert_eq!(secret.reveal(), "SECRET_KEY");
+    }
+}
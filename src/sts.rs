@@ -0,0 +1,241 @@
+//! STS assume-role support that populates a profile with temporary session
+//! credentials and can re-assume the role once they near expiry, mirroring
+//! the auto-refreshing STS provider pattern used by the AWS SDKs.
+
+use chrono::{Duration, Utc};
+
+use crate::{AWSCredentials, Credentials, Error};
+
+/// Parameters for an STS `AssumeRole` call.
+#[derive(Clone, Debug)]
+pub struct AssumeRoleParams {
+    pub role_arn: String,
+    pub session_name: String,
+    pub source_profile: Option<String>,
+    pub duration_seconds: Option<i64>,
+}
+
+impl AssumeRoleParams {
+    pub fn new<A: Into<String>, S: Into<String>>(role_arn: A, session_name: S) -> AssumeRoleParams {
+        AssumeRoleParams {
+            role_arn: role_arn.into(),
+            session_name: session_name.into(),
+            source_profile: None,
+            duration_seconds: None,
+        }
+    }
+}
+
+impl AWSCredentials {
+    /// Calls STS `AssumeRole` and stores the resulting session credentials,
+    /// including their expiration, into `target_profile`.
+    ///
+    /// When `params.source_profile` is set, the call is signed with that
+    /// profile's credentials (looked up on `self`) rather than the ambient
+    /// default credential chain. If that profile is not present on `self`,
+    /// the ambient default credential chain is used.
+    #[cfg(feature = "rusoto")]
+    pub async fn assume_role(
+        &mut self,
+        target_profile: &str,
+        params: &AssumeRoleParams,
+    ) -> Result<(), Error> {
+        use rusoto_core::{credential::StaticProvider, HttpClient, Region};
+        use rusoto_sts::{AssumeRoleRequest, Sts, StsClient};
+
+        let source_credentials = params
+            .source_profile
+            .as_deref()
+            .and_then(|profile| self.get_profile(profile));
+
+        let client = match source_credentials {
+            Some(credentials) => {
+                let provider = StaticProvider::new(
+                    credentials.access_key_id.clone(),
+                    credentials.secret_access_key.reveal().to_string(),
+                    credentials
+                        .session_token
+                        .as_ref()
+                        .map(|token| token.reveal().to_string()),
+                    None,
+                );
+                let http_client = HttpClient::new().map_err(|_| Error::FailedToParse)?;
+                StsClient::new_with(http_client, provider, Region::default())
+            }
+            None => StsClient::new(Region::default()),
+        };
+
+        let request = AssumeRoleRequest {
+            role_arn: params.role_arn.clone(),
+            role_session_name: params.session_name.clone(),
+            duration_seconds: params.duration_seconds,
+            ..Default::default()
+        };
+
+        let response = client
+            .assume_role(request)
+            .await
+            .map_err(|_| Error::FailedToParse)?;
+        let sts_credentials = response.credentials.ok_or(Error::FailedToParse)?;
+        let credentials: Credentials = sts_credentials.into();
+
+        self.set_profile(target_profile, &credentials);
+
+        Ok(())
+    }
+
+    /// Calls STS `AssumeRole` and stores the resulting session credentials,
+    /// including their expiration, into `target_profile`.
+    ///
+    /// When `params.source_profile` is set, the call is signed with that
+    /// profile's credentials (looked up on `self`) rather than the ambient
+    /// default credential chain. If that profile is not present on `self`,
+    /// the ambient default credential chain is used.
+    #[cfg(feature = "aws_sdk")]
+    pub async fn assume_role(
+        &mut self,
+        target_profile: &str,
+        params: &AssumeRoleParams,
+    ) -> Result<(), Error> {
+        let config = aws_config::load_from_env().await;
+
+        let source_credentials = params
+            .source_profile
+            .as_deref()
+            .and_then(|profile| self.get_profile(profile));
+
+        let client = match source_credentials {
+            Some(credentials) => {
+                let sdk_credentials = aws_sdk_sts::config::Credentials::new(
+                    credentials.access_key_id.clone(),
+                    credentials.secret_access_key.reveal().to_string(),
+                    credentials
+                        .session_token
+                        .as_ref()
+                        .map(|token| token.reveal().to_string()),
+                    None,
+                    "aws_cred",
+                );
+                let sts_config = aws_sdk_sts::config::Builder::from(&config)
+                    .credentials_provider(sdk_credentials)
+                    .build();
+                aws_sdk_sts::Client::from_conf(sts_config)
+            }
+            None => aws_sdk_sts::Client::new(&config),
+        };
+
+        let mut request = client
+            .assume_role()
+            .role_arn(&params.role_arn)
+            .role_session_name(&params.session_name);
+
+        if let Some(duration_seconds) = params.duration_seconds {
+            request = request.duration_seconds(duration_seconds as i32);
+        }
+
+        let response = request.send().await.map_err(|_| Error::FailedToParse)?;
+        let sts_credentials = response.credentials.ok_or(Error::FailedToParse)?;
+        let credentials: Credentials = sts_credentials
+            .try_into()
+            .map_err(|_| Error::FailedToParse)?;
+
+        self.set_profile(target_profile, &credentials);
+
+        Ok(())
+    }
+
+    /// Re-assumes the role for `target_profile` if its cached session is
+    /// missing, already expired, or expires within `skew`.
+    #[cfg(any(feature = "rusoto", feature = "aws_sdk"))]
+    pub async fn refresh_if_expired(
+        &mut self,
+        target_profile: &str,
+        params: &AssumeRoleParams,
+        skew: Duration,
+    ) -> Result<(), Error> {
+        let needs_refresh = match self.get_profile(target_profile) {
+            Some(credentials) => match credentials.expiration {
+                Some(expiration) => expiration - skew <= Utc::now(),
+                None => false,
+            },
+            None => true,
+        };
+
+        if needs_refresh {
+            self.assume_role(target_profile, params).await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(all(test, any(feature = "rusoto", feature = "aws_sdk")))]
+mod test {
+    use super::*;
+
+    fn credentials_expiring_in(seconds: i64) -> Credentials {
+        Credentials {
+            access_key_id: "ACCESS_KEY".to_string(),
+            secret_access_key: "SECRET_KEY".into(),
+            session_token: None,
+            expiration: Some(Utc::now() + Duration::seconds(seconds)),
+        }
+    }
+
+    #[tokio::test]
+    async fn refresh_if_expired_skips_when_outside_skew() {
+        let mut credentials = AWSCredentials::new("");
+        credentials.set_profile("session", &credentials_expiring_in(3600));
+        let params = AssumeRoleParams::new("arn:aws:iam::123456789012:role/test", "session");
+
+        credentials
+            .refresh_if_expired("session", &params, Duration::seconds(60))
+            .await
+            .unwrap();
+
+        // No assume_role call happened: the cached credentials are
+        // untouched because the expiration is outside the skew window.
+        assert_eq!(
+            credentials.get_profile("session").unwrap().access_key_id,
+            "ACCESS_KEY"
+        );
+    }
+
+    #[tokio::test]
+    async fn refresh_if_expired_attempts_refresh_within_skew() {
+        let mut credentials = AWSCredentials::new("");
+        credentials.set_profile("session", &credentials_expiring_in(30));
+        let params = AssumeRoleParams::new("arn:aws:iam::123456789012:role/test", "session");
+
+        // There's no real STS endpoint reachable in tests, so a refresh
+        // attempt surfaces as an error here; what this asserts is that a
+        // refresh was attempted at all once expiration falls inside `skew`.
+        let result = credentials
+            .refresh_if_expired("session", &params, Duration::seconds(60))
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn refresh_if_expired_attempts_refresh_when_already_expired() {
+        let mut credentials = AWSCredentials::new("");
+        credentials.set_profile("session", &credentials_expiring_in(-60));
+        let params = AssumeRoleParams::new("arn:aws:iam::123456789012:role/test", "session");
+
+        let result = credentials
+            .refresh_if_expired("session", &params, Duration::seconds(60))
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn refresh_if_expired_attempts_refresh_when_profile_missing() {
+        let mut credentials = AWSCredentials::new("");
+        let params = AssumeRoleParams::new("arn:aws:iam::123456789012:role/test", "session");
+
+        let result = credentials
+            .refresh_if_expired("session", &params, Duration::seconds(60))
+            .await;
+        assert!(result.is_err());
+    }
+}
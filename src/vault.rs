@@ -0,0 +1,313 @@
+//! An opt-in, encrypted-at-rest alternative to the plaintext credentials
+//! file: [`AWSCredentials::write_encrypted`] derives a key from a passphrase
+//! and a random salt, encrypts each profile's secret access key and session
+//! token with an authenticated cipher, and persists the salt, a per-value
+//! nonce, and the ciphertext. [`AWSCredentials::load_encrypted`] re-derives
+//! the key and checks it against a known `verify_blob` before attempting to
+//! decrypt anything else, so a wrong passphrase fails fast with a clear
+//! [`Error`] instead of producing garbage credentials.
+//!
+//! Access key IDs are not secret and remain in cleartext.
+
+use aes_gcm::{
+    aead::{rand_core::RngCore, Aead, AeadCore, KeyInit, OsRng},
+    Aes256Gcm, Key, Nonce,
+};
+use argon2::Argon2;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chrono::{DateTime, Utc};
+use std::{
+    collections::HashMap,
+    fs::OpenOptions,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use crate::{AWSCredentials, Credentials, Error};
+
+/// Known plaintext encrypted alongside the real data so a wrong passphrase
+/// can be detected before any profile is decrypted.
+const VERIFY_PLAINTEXT: &[u8] = b"aws_cred-vault-v1";
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<Key<Aes256Gcm>, Error> {
+    let mut key_bytes = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key_bytes)
+        .map_err(|_| Error::FailedToParse)?;
+    Ok(*Key::<Aes256Gcm>::from_slice(&key_bytes))
+}
+
+fn encrypt_value(cipher: &Aes256Gcm, value: &str) -> Result<(Vec<u8>, Vec<u8>), Error> {
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, value.as_bytes())
+        .map_err(|_| Error::FailedToParse)?;
+    Ok((nonce.to_vec(), ciphertext))
+}
+
+fn decrypt_value(cipher: &Aes256Gcm, nonce: &[u8], ciphertext: &[u8]) -> Result<String, Error> {
+    let nonce = Nonce::from_slice(nonce);
+    let plaintext = cipher
+        .decrypt(nonce, ciphertext)
+        .map_err(|_| Error::FailedToParse)?;
+    String::from_utf8(plaintext).map_err(|_| Error::FailedToParse)
+}
+
+impl AWSCredentials {
+    /// Encrypts every profile's secrets with a key derived from `passphrase`
+    /// and writes the result to `path`. Access key IDs are kept in cleartext.
+    pub fn write_encrypted<P: AsRef<Path>>(
+        &self,
+        path: P,
+        passphrase: &str,
+    ) -> Result<(), Error> {
+        let mut salt = [0u8; 16];
+        OsRng.fill_bytes(&mut salt);
+        let cipher = Aes256Gcm::new(&derive_key(passphrase, &salt)?);
+
+        let (verify_nonce, verify_blob) = encrypt_value(
+            &cipher,
+            std::str::from_utf8(VERIFY_PLAINTEXT).unwrap(),
+        )?;
+
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)
+            .map_err(|_| Error::FileNotFound(self.file_path.to_string()))?;
+        let mut writer = BufWriter::new(file);
+
+        writeln!(writer, "salt = {}", BASE64.encode(salt.as_slice())).unwrap();
+        writeln!(writer, "verify_nonce = {}", BASE64.encode(&verify_nonce)).unwrap();
+        writeln!(writer, "verify_blob = {}", BASE64.encode(&verify_blob)).unwrap();
+        writeln!(writer).unwrap();
+
+        for (section, creds) in &self.credentials {
+            writeln!(writer, "[{}]", section).unwrap();
+            writeln!(writer, "aws_access_key_id = {}", creds.access_key_id).unwrap();
+
+            let (secret_nonce, secret_ciphertext) =
+                encrypt_value(&cipher, creds.secret_access_key.reveal())?;
+            writeln!(
+                writer,
+                "aws_secret_access_key_nonce = {}",
+                BASE64.encode(secret_nonce)
+            )
+            .unwrap();
+            writeln!(
+                writer,
+                "aws_secret_access_key = {}",
+                BASE64.encode(secret_ciphertext)
+            )
+            .unwrap();
+
+            if let Some(session_token) = &creds.session_token {
+                let (token_nonce, token_ciphertext) =
+                    encrypt_value(&cipher, session_token.reveal())?;
+                writeln!(
+                    writer,
+                    "aws_session_token_nonce = {}",
+                    BASE64.encode(token_nonce)
+                )
+                .unwrap();
+                writeln!(
+                    writer,
+                    "aws_session_token = {}",
+                    BASE64.encode(token_ciphertext)
+                )
+                .unwrap();
+            }
+
+            if let Some(expiration) = &creds.expiration {
+                writeln!(writer, "aws_expiration = {}", expiration.to_rfc3339()).unwrap();
+            }
+
+            writeln!(writer).unwrap();
+        }
+
+        Ok(())
+    }
+
+    /// Loads credentials written by [`AWSCredentials::write_encrypted`],
+    /// confirming `passphrase` against the stored `verify_blob` before
+    /// decrypting any profile.
+    pub fn load_encrypted<P: AsRef<Path>>(
+        path: P,
+        passphrase: &str,
+    ) -> Result<AWSCredentials, Error> {
+        let file_path = path.as_ref().to_str().unwrap().to_string();
+        let data = std::fs::read_to_string(&path)
+            .map_err(|_| Error::FileNotFound(file_path.clone()))?;
+
+        let mut top_level = HashMap::new();
+        let mut sections: HashMap<String, HashMap<String, String>> = HashMap::new();
+        let mut current_section: Option<String> = None;
+
+        for line in data.lines() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if line.starts_with('[') && line.ends_with(']') {
+                current_section = Some(line[1..line.len() - 1].to_string());
+                continue;
+            }
+
+            let parts: Vec<&str> = line.splitn(2, '=').collect();
+            if parts.len() != 2 {
+                continue;
+            }
+            let key = parts[0].trim().to_string();
+            let value = parts[1].trim().to_string();
+
+            match &current_section {
+                Some(section) => {
+                    sections.entry(section.clone()).or_default().insert(key, value);
+                }
+                None => {
+                    top_level.insert(key, value);
+                }
+            }
+        }
+
+        let salt = BASE64
+            .decode(top_level.get("salt").ok_or(Error::FailedToParse)?)
+            .map_err(|_| Error::FailedToParse)?;
+        let verify_nonce = BASE64
+            .decode(top_level.get("verify_nonce").ok_or(Error::FailedToParse)?)
+            .map_err(|_| Error::FailedToParse)?;
+        let verify_blob = BASE64
+            .decode(top_level.get("verify_blob").ok_or(Error::FailedToParse)?)
+            .map_err(|_| Error::FailedToParse)?;
+
+        let cipher = Aes256Gcm::new(&derive_key(passphrase, &salt)?);
+
+        let verified = decrypt_value(&cipher, &verify_nonce, &verify_blob)
+            .map_err(|_| Error::InvalidPassphrase)?;
+        if verified.as_bytes() != VERIFY_PLAINTEXT {
+            return Err(Error::InvalidPassphrase);
+        }
+
+        let mut credentials = HashMap::new();
+        for (section, settings) in sections {
+            let access_key_id = settings.get("aws_access_key_id").cloned().unwrap_or_default();
+
+            let secret_nonce = BASE64
+                .decode(
+                    settings
+                        .get("aws_secret_access_key_nonce")
+                        .ok_or(Error::FailedToParse)?,
+                )
+                .map_err(|_| Error::FailedToParse)?;
+            let secret_ciphertext = BASE64
+                .decode(
+                    settings
+                        .get("aws_secret_access_key")
+                        .ok_or(Error::FailedToParse)?,
+                )
+                .map_err(|_| Error::FailedToParse)?;
+            let secret_access_key =
+                decrypt_value(&cipher, &secret_nonce, &secret_ciphertext)?.into();
+
+            let session_token = match (
+                settings.get("aws_session_token_nonce"),
+                settings.get("aws_session_token"),
+            ) {
+                (Some(nonce), Some(ciphertext)) => {
+                    let nonce = BASE64.decode(nonce).map_err(|_| Error::FailedToParse)?;
+                    let ciphertext = BASE64.decode(ciphertext).map_err(|_| Error::FailedToParse)?;
+                    Some(decrypt_value(&cipher, &nonce, &ciphertext)?.into())
+                }
+                _ => None,
+            };
+
+            let expiration = settings
+                .get("aws_expiration")
+                .and_then(|value| DateTime::parse_from_rfc3339(value).ok())
+                .map(|dt| dt.with_timezone(&Utc));
+
+            credentials.insert(
+                section,
+                Credentials {
+                    access_key_id,
+                    secret_access_key,
+                    session_token,
+                    expiration,
+                },
+            );
+        }
+
+        Ok(AWSCredentials {
+            file_path,
+            credentials,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn round_trips_credentials_through_encryption() {
+        let temp_file = tempfile::NamedTempFile::new().unwrap();
+        let path = temp_file.path();
+
+        let mut credentials = AWSCredentials::new(path);
+        credentials
+            .with_profile("default")
+            .set_access_key_id("ACCESS_KEY")
+            .set_secret_access_key("SECRET_KEY")
+            .set_session_token(Some("SESSION_TOKEN".to_string()));
+        credentials.write_encrypted(path, "correct horse battery staple").unwrap();
+
+        let loaded = AWSCredentials::load_encrypted(path, "correct horse battery staple").unwrap();
+        let profile = loaded.get_profile("default").unwrap();
+        assert_eq!(profile.access_key_id, "ACCESS_KEY");
+        assert_eq!(profile.secret_access_key.reveal(), "SECRET_KEY");
+        assert_eq!(
+            profile.session_token.unwrap().reveal(),
+            "SESSION_TOKEN"
+        );
+    }
+
+    #[test]
+    fn round_trips_expiration() {
+        let temp_file = tempfile::NamedTempFile::new().unwrap();
+        let path = temp_file.path();
+
+        let expiration = Utc::now() + chrono::Duration::seconds(3600);
+        let mut credentials = AWSCredentials::new(path);
+        credentials
+            .with_profile("default")
+            .set_access_key_id("ACCESS_KEY")
+            .set_secret_access_key("SECRET_KEY");
+        credentials
+            .get_profile_mut("default")
+            .unwrap()
+            .expiration = Some(expiration);
+        credentials.write_encrypted(path, "correct horse battery staple").unwrap();
+
+        let loaded = AWSCredentials::load_encrypted(path, "correct horse battery staple").unwrap();
+        let profile = loaded.get_profile("default").unwrap();
+        assert_eq!(profile.expiration.unwrap().to_rfc3339(), expiration.to_rfc3339());
+    }
+
+    #[test]
+    fn rejects_a_wrong_passphrase() {
+        let temp_file = tempfile::NamedTempFile::new().unwrap();
+        let path = temp_file.path();
+
+        let mut credentials = AWSCredentials::new(path);
+        credentials
+            .with_profile("default")
+            .set_access_key_id("ACCESS_KEY")
+            .set_secret_access_key("SECRET_KEY");
+        credentials.write_encrypted(path, "correct horse battery staple").unwrap();
+
+        let result = AWSCredentials::load_encrypted(path, "wrong passphrase");
+        assert!(matches!(result, Err(Error::InvalidPassphrase)));
+    }
+}